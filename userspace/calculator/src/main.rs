@@ -1,114 +1,119 @@
 #![no_std]
 #![no_main]
+#![cfg_attr(test, feature(custom_test_frameworks))]
+#![cfg_attr(test, test_runner(mtos_runtime::test_runner))]
+#![cfg_attr(test, reexport_test_harness_main = "test_main")]
 
 //! Calculator userspace application for MTOS
-//! 
+//!
 //! Demonstrates more complex userspace functionality including
 //! mathematical operations and memory management.
 
-use mtos_runtime::{println, getpid, mtos_main, format_u32};
-use heapless::String;
+use mtos_runtime::{getpid, malloc, free, mtos_main, println, Instant};
 
 fn main() -> i32 {
-    println("🧮 MTOS Calculator Application").unwrap();
-    println("==============================").unwrap();
-    println("").unwrap();
-    
+    println!("🧮 MTOS Calculator Application");
+    println!("==============================");
+    println!();
+
     let pid = getpid();
-    println(&format!("Running as PID: {}", format_u32(pid))).unwrap();
-    println("").unwrap();
-    
+    println!("Running as PID: {}", pid);
+    println!();
+
     // Demonstrate basic arithmetic
-    println("📊 Basic Arithmetic Operations:").unwrap();
-    
+    println!("📊 Basic Arithmetic Operations:");
+
     let a = 42;
     let b = 17;
-    
-    println(&format!("Numbers: {} and {}", format_u32(a), format_u32(b))).unwrap();
-    
+
+    println!("Numbers: {} and {}", a, b);
+
     // Addition
     let sum = a + b;
-    println(&format!("Addition: {} + {} = {}", format_u32(a), format_u32(b), format_u32(sum))).unwrap();
-    
+    println!("Addition: {} + {} = {}", a, b, sum);
+
     // Subtraction
     let diff = a - b;
-    println(&format!("Subtraction: {} - {} = {}", format_u32(a), format_u32(b), format_u32(diff))).unwrap();
-    
+    println!("Subtraction: {} - {} = {}", a, b, diff);
+
     // Multiplication
     let product = a * b;
-    println(&format!("Multiplication: {} × {} = {}", format_u32(a), format_u32(b), format_u32(product))).unwrap();
-    
+    println!("Multiplication: {} × {} = {}", a, b, product);
+
     // Division
     let quotient = a / b;
     let remainder = a % b;
-    println(&format!("Division: {} ÷ {} = {} remainder {}", 
-                   format_u32(a), format_u32(b), format_u32(quotient), format_u32(remainder))).unwrap();
-    
-    println("").unwrap();
-    
+    println!("Division: {} ÷ {} = {} remainder {}", a, b, quotient, remainder);
+
+    println!();
+
     // Demonstrate more complex operations
-    println("🔬 Advanced Operations:").unwrap();
-    
+    println!("🔬 Advanced Operations:");
+
     // Square calculation
     let square = a * a;
-    println(&format!("Square of {}: {}", format_u32(a), format_u32(square))).unwrap();
-    
+    println!("Square of {}: {}", a, square);
+
     // Simple power calculation (a^3)
     let cube = a * a * a;
-    println(&format!("Cube of {}: {}", format_u32(a), format_u32(cube))).unwrap();
-    
+    println!("Cube of {}: {}", a, cube);
+
     // Factorial calculation (for small numbers)
     let factorial_num = 5;
     let factorial = calculate_factorial(factorial_num);
-    println(&format!("Factorial of {}: {}", format_u32(factorial_num), format_u32(factorial))).unwrap();
-    
+    println!("Factorial of {}: {}", factorial_num, factorial);
+
     // Fibonacci sequence
-    println("").unwrap();
-    println("🌀 Fibonacci Sequence (first 10 numbers):").unwrap();
+    println!();
+    println!("🌀 Fibonacci Sequence (first 10 numbers):");
+    let fib_start = Instant::now();
     for i in 0..10 {
         let fib = fibonacci(i);
-        println(&format!("F({}) = {}", format_u32(i), format_u32(fib))).unwrap();
+        println!("F({}) = {}", i, fib);
     }
-    
+    println!("(computed in {} ms)", fib_start.elapsed_ms());
+
     // Prime number check
-    println("").unwrap();
-    println("🔍 Prime Number Analysis:").unwrap();
+    println!();
+    println!("🔍 Prime Number Analysis:");
+    let prime_start = Instant::now();
     for num in 2..20 {
         if is_prime(num) {
-            println(&format!("{} is prime", format_u32(num))).unwrap();
+            println!("{} is prime", num);
         }
     }
-    
+    println!("(computed in {} ms)", prime_start.elapsed_ms());
+
     // Memory usage demonstration
-    println("").unwrap();
-    println("🧠 Memory Operations:").unwrap();
-    
+    println!();
+    println!("🧠 Memory Operations:");
+
     // Allocate some memory for calculations
-    match mtos_runtime::malloc(256) {
+    match malloc(256) {
         Ok(ptr) => {
-            println("✅ Allocated 256 bytes for calculations").unwrap();
-            
+            println!("✅ Allocated 256 bytes for calculations");
+
             // Simulate some work with the memory
             // (In a real implementation, we'd use this memory)
-            
-            match mtos_runtime::free(ptr) {
-                Ok(_) => println("✅ Memory freed successfully").unwrap(),
-                Err(e) => println(&format!("⚠️ Failed to free memory: {}", e)).unwrap(),
+
+            match free(ptr) {
+                Ok(_) => println!("✅ Memory freed successfully"),
+                Err(e) => println!("⚠️ Failed to free memory: {}", e),
             }
         }
         Err(e) => {
-            println(&format!("❌ Memory allocation failed: {}", e)).unwrap();
+            println!("❌ Memory allocation failed: {}", e);
         }
     }
-    
-    println("").unwrap();
-    println("🎉 Calculator operations completed successfully!").unwrap();
-    println("📝 Educational Notes:").unwrap();
-    println("  • All calculations performed in userspace").unwrap();
-    println("  • Memory management handled by kernel allocator").unwrap();
-    println("  • System calls used for I/O operations").unwrap();
-    println("  • Demonstrates Rust's no_std capabilities").unwrap();
-    
+
+    println!();
+    println!("🎉 Calculator operations completed successfully!");
+    println!("📝 Educational Notes:");
+    println!("  • All calculations performed in userspace");
+    println!("  • Memory management handled by kernel allocator");
+    println!("  • System calls used for I/O operations");
+    println!("  • Demonstrates Rust's no_std capabilities");
+
     0
 }
 
@@ -117,7 +122,7 @@ fn calculate_factorial(n: u32) -> u32 {
     if n <= 1 {
         return 1;
     }
-    
+
     let mut result = 1;
     for i in 2..=n {
         result *= i;
@@ -154,7 +159,7 @@ fn is_prime(n: u32) -> bool {
     if n % 2 == 0 {
         return false;
     }
-    
+
     let mut i = 3;
     while i * i <= n {
         if n % i == 0 {
@@ -165,29 +170,32 @@ fn is_prime(n: u32) -> bool {
     true
 }
 
-// Helper function to format strings
-fn format(template: &str, value: u32) -> String<64> {
-    let mut result = String::new();
-    let value_str = format_u32(value);
-    
-    // Simple string substitution (replace first {} with value)
-    let mut found_placeholder = false;
-    for ch in template.chars() {
-        if ch == '{' && !found_placeholder {
-            // Start of placeholder - skip until '}'
-            found_placeholder = true;
-        } else if ch == '}' && found_placeholder {
-            // End of placeholder - insert value
-            for value_ch in value_str.chars() {
-                result.push(value_ch).ok();
-            }
-            found_placeholder = false;
-        } else if !found_placeholder {
-            result.push(ch).ok();
-        }
-    }
-    
-    result
+#[cfg(test)]
+use mtos_runtime::assert_eq_u32;
+
+#[cfg(test)]
+#[test_case]
+fn test_calculate_factorial() -> bool {
+    assert_eq_u32!(1, calculate_factorial(0))
+        & assert_eq_u32!(1, calculate_factorial(1))
+        & assert_eq_u32!(120, calculate_factorial(5))
+}
+
+#[cfg(test)]
+#[test_case]
+fn test_fibonacci() -> bool {
+    assert_eq_u32!(0, fibonacci(0))
+        & assert_eq_u32!(1, fibonacci(1))
+        & assert_eq_u32!(34, fibonacci(9))
+}
+
+#[cfg(test)]
+#[test_case]
+fn test_is_prime() -> bool {
+    assert_eq_u32!(0, is_prime(1) as u32)
+        & assert_eq_u32!(1, is_prime(2) as u32)
+        & assert_eq_u32!(1, is_prime(17) as u32)
+        & assert_eq_u32!(0, is_prime(18) as u32)
 }
 
 mtos_main!(main);
@@ -0,0 +1,84 @@
+//! `core::fmt::Write` sink for the console, backing the `print!`/`println!` macros.
+//!
+//! Buffers formatted output into a `heapless::String` and flushes it via
+//! the `Print` syscall.
+
+use core::fmt;
+
+use crate::print;
+
+/// Maximum number of bytes buffered between flushes.
+const BUFFER_LEN: usize = 256;
+
+/// `core::fmt::Write` sink that flushes buffered output via the `Print` syscall.
+pub struct ConsoleWriter {
+    buffer: heapless::String<BUFFER_LEN>,
+}
+
+impl ConsoleWriter {
+    pub fn new() -> Self {
+        Self {
+            buffer: heapless::String::new(),
+        }
+    }
+
+    fn flush(&mut self) {
+        if !self.buffer.is_empty() {
+            print(&self.buffer).ok();
+            self.buffer.clear();
+        }
+    }
+}
+
+impl fmt::Write for ConsoleWriter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            if self.buffer.push(ch).is_err() {
+                self.flush();
+                // If a single character still doesn't fit the freshly
+                // cleared buffer, there is nothing more we can do with it.
+                self.buffer.push(ch).ok();
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Drop for ConsoleWriter {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+impl Default for ConsoleWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Format `args` and print the result to the console.
+pub fn print_fmt(args: fmt::Arguments) {
+    use fmt::Write;
+    let mut writer = ConsoleWriter::new();
+    let _ = writer.write_fmt(args);
+}
+
+/// Print formatted text to the console, like `std::print!`.
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::print_fmt(core::format_args!($($arg)*))
+    };
+}
+
+/// Print formatted text to the console followed by a newline, like `std::println!`.
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print_fmt(core::format_args!("\n"))
+    };
+    ($($arg:tt)*) => {{
+        $crate::print_fmt(core::format_args!($($arg)*));
+        $crate::print_fmt(core::format_args!("\n"));
+    }};
+}
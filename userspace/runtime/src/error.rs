@@ -0,0 +1,48 @@
+//! Structured syscall error type.
+//!
+//! Gives negative syscall return codes names and a `Display` impl instead
+//! of leaving callers to print the bare code.
+
+use core::fmt;
+
+/// Error returned by a failed MTOS system call.
+#[non_exhaustive]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SysError {
+    InvalidArgument,
+    OutOfMemory,
+    NoSuchProcess,
+    WouldBlock,
+    Interrupted,
+    Faulted,
+    Unknown(i32),
+}
+
+impl SysError {
+    /// Map a negative syscall return code to a `SysError`.
+    pub fn from_errno(raw: isize) -> SysError {
+        match raw {
+            -1 => SysError::InvalidArgument,
+            -2 => SysError::OutOfMemory,
+            -3 => SysError::NoSuchProcess,
+            -4 => SysError::WouldBlock,
+            -5 => SysError::Interrupted,
+            -6 => SysError::Faulted,
+            other => SysError::Unknown(other as i32),
+        }
+    }
+}
+
+impl fmt::Display for SysError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SysError::InvalidArgument => write!(f, "invalid argument"),
+            SysError::OutOfMemory => write!(f, "out of memory"),
+            SysError::NoSuchProcess => write!(f, "no such process"),
+            SysError::WouldBlock => write!(f, "would block"),
+            SysError::Interrupted => write!(f, "interrupted"),
+            SysError::Faulted => write!(f, "fault"),
+            SysError::Unknown(code) => write!(f, "unknown error ({})", code),
+        }
+    }
+}
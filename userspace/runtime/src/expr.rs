@@ -0,0 +1,198 @@
+//! Infix expression evaluator for the shell's `calc` command.
+//!
+//! Tokenizes an arbitrary infix expression and evaluates it with the
+//! shunting-yard algorithm.
+
+use heapless::Vec;
+
+/// Maximum number of tokens (operators, operands and parentheses) in one expression.
+const MAX_TOKENS: usize = 32;
+
+/// Errors produced while tokenizing or evaluating an expression.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CalcError {
+    MismatchedParens,
+    DivisionByZero,
+    UnexpectedToken,
+    TooManyTokens,
+    EmptyExpression,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(i64),
+    Op(char),
+    LParen,
+    RParen,
+}
+
+/// Evaluate an arbitrary infix expression, e.g. `(15 + 27) * 3 ^ 2`.
+pub fn eval_expr(input: &str) -> Result<i64, CalcError> {
+    let tokens = tokenize(input)?;
+    let rpn = to_rpn(&tokens)?;
+    eval_rpn(&rpn)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token, MAX_TOKENS>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        if ch.is_ascii_digit() {
+            let mut value: i64 = 0;
+            while let Some(&digit_ch) = chars.peek() {
+                match digit_ch.to_digit(10) {
+                    Some(digit) => {
+                        value = value * 10 + digit as i64;
+                        chars.next();
+                    }
+                    None => break,
+                }
+            }
+            tokens
+                .push(Token::Number(value))
+                .map_err(|_| CalcError::TooManyTokens)?;
+            continue;
+        }
+
+        let token = match ch {
+            '+' | '-' | '*' | '/' | '%' | '^' => Token::Op(ch),
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            _ => return Err(CalcError::UnexpectedToken),
+        };
+        tokens.push(token).map_err(|_| CalcError::TooManyTokens)?;
+        chars.next();
+    }
+
+    if tokens.is_empty() {
+        return Err(CalcError::EmptyExpression);
+    }
+
+    Ok(tokens)
+}
+
+fn precedence(op: char) -> u8 {
+    match op {
+        '+' | '-' => 1,
+        '*' | '/' | '%' => 2,
+        '^' => 3,
+        _ => 0,
+    }
+}
+
+fn is_right_associative(op: char) -> bool {
+    op == '^'
+}
+
+/// Convert infix tokens to reverse-Polish order with the shunting-yard
+/// algorithm: numbers go straight to the output, operators are held on a
+/// stack and popped into the output whenever a lower-or-equal precedence
+/// operator arrives (strictly lower for right-associative `^`).
+fn to_rpn(tokens: &[Token]) -> Result<Vec<Token, MAX_TOKENS>, CalcError> {
+    let mut output: Vec<Token, MAX_TOKENS> = Vec::new();
+    let mut operators: Vec<Token, MAX_TOKENS> = Vec::new();
+
+    for &token in tokens {
+        match token {
+            Token::Number(_) => {
+                output.push(token).map_err(|_| CalcError::TooManyTokens)?;
+            }
+            Token::Op(op) => {
+                while let Some(&Token::Op(top)) = operators.last() {
+                    let should_pop = if is_right_associative(op) {
+                        precedence(top) > precedence(op)
+                    } else {
+                        precedence(top) >= precedence(op)
+                    };
+                    if !should_pop {
+                        break;
+                    }
+                    let popped = operators.pop().unwrap();
+                    output.push(popped).map_err(|_| CalcError::TooManyTokens)?;
+                }
+                operators.push(token).map_err(|_| CalcError::TooManyTokens)?;
+            }
+            Token::LParen => {
+                operators.push(token).map_err(|_| CalcError::TooManyTokens)?;
+            }
+            Token::RParen => loop {
+                match operators.pop() {
+                    Some(Token::LParen) => break,
+                    Some(op_token) => {
+                        output.push(op_token).map_err(|_| CalcError::TooManyTokens)?;
+                    }
+                    None => return Err(CalcError::MismatchedParens),
+                }
+            },
+        }
+    }
+
+    while let Some(token) = operators.pop() {
+        if token == Token::LParen {
+            return Err(CalcError::MismatchedParens);
+        }
+        output.push(token).map_err(|_| CalcError::TooManyTokens)?;
+    }
+
+    Ok(output)
+}
+
+fn eval_rpn(rpn: &[Token]) -> Result<i64, CalcError> {
+    let mut stack: Vec<i64, MAX_TOKENS> = Vec::new();
+
+    for &token in rpn {
+        match token {
+            Token::Number(n) => {
+                stack.push(n).map_err(|_| CalcError::TooManyTokens)?;
+            }
+            Token::Op(op) => {
+                let b = stack.pop().ok_or(CalcError::UnexpectedToken)?;
+                let a = stack.pop().ok_or(CalcError::UnexpectedToken)?;
+                let result = match op {
+                    '+' => a + b,
+                    '-' => a - b,
+                    '*' => a * b,
+                    '/' => {
+                        if b == 0 {
+                            return Err(CalcError::DivisionByZero);
+                        }
+                        a / b
+                    }
+                    '%' => {
+                        if b == 0 {
+                            return Err(CalcError::DivisionByZero);
+                        }
+                        a % b
+                    }
+                    '^' => integer_pow(a, b),
+                    _ => return Err(CalcError::UnexpectedToken),
+                };
+                stack.push(result).map_err(|_| CalcError::TooManyTokens)?;
+            }
+            Token::LParen | Token::RParen => return Err(CalcError::MismatchedParens),
+        }
+    }
+
+    if stack.len() != 1 {
+        return Err(CalcError::UnexpectedToken);
+    }
+
+    stack.pop().ok_or(CalcError::EmptyExpression)
+}
+
+fn integer_pow(base: i64, exp: i64) -> i64 {
+    if exp <= 0 {
+        return 1;
+    }
+    let mut result = 1;
+    for _ in 0..exp {
+        result *= base;
+    }
+    result
+}
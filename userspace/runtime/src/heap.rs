@@ -0,0 +1,176 @@
+//! Userspace heap allocator layered over kernel-provided arenas.
+//!
+//! Sub-allocates from arenas obtained from the kernel with an
+//! address-ordered free list, rounding each request up to satisfy
+//! `Layout::align()`, coalescing adjacent freed blocks back together on
+//! `dealloc`, and requesting a fresh syscall-backed arena whenever the
+//! existing free list can't fit a request.
+
+use core::alloc::Layout;
+use core::mem::{align_of, size_of};
+use core::ptr;
+
+use crate::malloc;
+
+/// Size of each arena requested from the kernel.
+const ARENA_SIZE: usize = 64 * 1024;
+
+/// Smallest leftover worth splitting into its own free block; anything
+/// smaller is donated to the allocation that carved it off.
+const MIN_SPLIT: usize = size_of::<FreeBlock>() * 2;
+
+/// Node of the free list, written directly into the free memory it describes.
+#[repr(C)]
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+/// Header written just before the pointer handed back to the caller, so
+/// `dealloc` can recover the original (pre-alignment) block bounds.
+#[repr(C)]
+struct BlockHeader {
+    region_start: usize,
+    region_size: usize,
+}
+
+const HEADER_SIZE: usize = size_of::<BlockHeader>();
+
+fn align_up(addr: usize, align: usize) -> usize {
+    (addr + align - 1) & !(align - 1)
+}
+
+/// Free-list heap sub-allocating from one or more arenas obtained from the kernel.
+pub struct Heap {
+    free_list: *mut FreeBlock,
+}
+
+impl Heap {
+    pub const fn empty() -> Self {
+        Self {
+            free_list: ptr::null_mut(),
+        }
+    }
+
+    /// Request a fresh arena of at least `min_size` bytes from the kernel
+    /// and add it to the free list.
+    unsafe fn grow(&mut self, min_size: usize) -> bool {
+        let region_size = min_size.max(ARENA_SIZE);
+        match malloc(region_size) {
+            Ok(ptr) => {
+                self.insert_free(ptr, region_size);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    pub unsafe fn alloc(&mut self, layout: Layout) -> *mut u8 {
+        let align = layout.align().max(align_of::<usize>());
+        // Round up so `data_end` (and the free-list header `insert_free`
+        // later writes there) lands on an aligned address too.
+        let size = align_up(layout.size().max(size_of::<FreeBlock>()), align_of::<FreeBlock>());
+
+        loop {
+            if let Some(ptr) = self.take_fit(align, size) {
+                return ptr;
+            }
+
+            // The existing free list has nothing big enough (or the heap
+            // hasn't been seeded yet) -- request a fresh syscall-backed
+            // arena sized to comfortably fit this allocation and retry.
+            if !self.grow(size + HEADER_SIZE + align) {
+                return ptr::null_mut();
+            }
+        }
+    }
+
+    /// Walk the free list for a block that fits `size` bytes aligned to
+    /// `align`, splitting off any leftover remainder, and return the
+    /// data pointer if one was found.
+    unsafe fn take_fit(&mut self, align: usize, size: usize) -> Option<*mut u8> {
+        let mut prev: *mut FreeBlock = ptr::null_mut();
+        let mut current = self.free_list;
+
+        while !current.is_null() {
+            let region_start = current as usize;
+            let region_end = region_start + (*current).size;
+
+            let data_start = align_up(region_start + HEADER_SIZE, align);
+            let data_end = data_start + size;
+
+            if data_end <= region_end {
+                let next = (*current).next;
+                if prev.is_null() {
+                    self.free_list = next;
+                } else {
+                    (*prev).next = next;
+                }
+
+                let remainder = region_end - data_end;
+                let block_end = if remainder >= MIN_SPLIT {
+                    self.insert_free(data_end as *mut u8, remainder);
+                    data_end
+                } else {
+                    // Too small to be useful on its own; donate it to this block.
+                    region_end
+                };
+
+                let header = (data_start - HEADER_SIZE) as *mut BlockHeader;
+                (*header).region_start = region_start;
+                (*header).region_size = block_end - region_start;
+
+                return Some(data_start as *mut u8);
+            }
+
+            prev = current;
+            current = (*current).next;
+        }
+
+        None
+    }
+
+    pub unsafe fn dealloc(&mut self, ptr: *mut u8) {
+        let header = (ptr as usize - HEADER_SIZE) as *mut BlockHeader;
+        let region_start = (*header).region_start;
+        let region_size = (*header).region_size;
+        self.insert_free(region_start as *mut u8, region_size);
+    }
+
+    /// Insert a freed region into the address-ordered free list, merging
+    /// it with the immediately preceding and/or following block when
+    /// they are contiguous in memory.
+    unsafe fn insert_free(&mut self, start: *mut u8, size: usize) {
+        let start_addr = start as usize;
+        let end_addr = start_addr + size;
+
+        let mut prev: *mut FreeBlock = ptr::null_mut();
+        let mut current = self.free_list;
+        while !current.is_null() && (current as usize) < start_addr {
+            prev = current;
+            current = (*current).next;
+        }
+
+        let mut new_size = size;
+        let mut new_next = current;
+
+        if !current.is_null() && end_addr == current as usize {
+            new_size += (*current).size;
+            new_next = (*current).next;
+        }
+
+        if !prev.is_null() && (prev as usize) + (*prev).size == start_addr {
+            (*prev).size += new_size;
+            (*prev).next = new_next;
+        } else {
+            let node = start as *mut FreeBlock;
+            (*node).size = new_size;
+            (*node).next = new_next;
+            if prev.is_null() {
+                self.free_list = node;
+            } else {
+                (*prev).next = node;
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Typed request/response RPC layer over `send_message`/`receive_message`.
+//!
+//! Frames each message as a 2-byte little-endian tag header followed by
+//! the raw payload bytes, so services can dispatch by tag. See the
+//! `sysinfo` app for `serve` and the shell's `info` command for `call`.
+
+use heapless::Vec;
+
+use crate::{receive_message, send_message, SysError};
+
+/// Maximum frame size (tag header + payload) moved through one message.
+const MAX_FRAME: usize = 256;
+
+/// Types that can be viewed as a raw byte representation for sending over IPC.
+///
+/// # Safety
+/// Implementors must be plain-old-data: no padding bytes observed as
+/// uninitialized, no pointers or references, and an identical layout on
+/// sender and receiver.
+pub unsafe trait AsBytes: Sized {
+    fn as_bytes(&self) -> &[u8] {
+        unsafe {
+            core::slice::from_raw_parts(
+                self as *const Self as *const u8,
+                core::mem::size_of::<Self>(),
+            )
+        }
+    }
+}
+
+unsafe impl AsBytes for u8 {}
+unsafe impl AsBytes for u16 {}
+unsafe impl AsBytes for u32 {}
+unsafe impl AsBytes for u64 {}
+unsafe impl AsBytes for i32 {}
+unsafe impl AsBytes for i64 {}
+
+/// A `serve` handler's reply; `None` sends nothing back to the sender.
+pub enum Response<'a> {
+    Reply(&'a [u8]),
+    None,
+}
+
+fn frame_of(tag: u16, payload: &[u8]) -> Result<Vec<u8, MAX_FRAME>, SysError> {
+    let mut frame: Vec<u8, MAX_FRAME> = Vec::new();
+    frame
+        .extend_from_slice(&tag.to_le_bytes())
+        .map_err(|_| SysError::InvalidArgument)?;
+    frame
+        .extend_from_slice(payload)
+        .map_err(|_| SysError::InvalidArgument)?;
+    Ok(frame)
+}
+
+/// Send `payload` to `dest`, framed as a 2-byte tag followed by its raw bytes.
+pub fn send_typed<T: AsBytes>(dest: u32, tag: u16, payload: &T) -> Result<(), SysError> {
+    let frame = frame_of(tag, payload.as_bytes())?;
+    send_message(dest, &frame)
+}
+
+/// Block on `receive_message` forever, dispatching each frame by its tag
+/// to `handler` and replying to the sender when it returns `Response::Reply`.
+pub fn serve(mut handler: impl FnMut(u32, u16, &[u8]) -> Response) -> ! {
+    let mut buf = [0u8; MAX_FRAME];
+    loop {
+        let (sender, len) = match receive_message(&mut buf) {
+            Ok(received) => received,
+            Err(_) => continue,
+        };
+        if len < 2 {
+            continue;
+        }
+
+        let tag = u16::from_le_bytes([buf[0], buf[1]]);
+        let payload = &buf[2..len];
+
+        if let Response::Reply(reply) = handler(sender, tag, payload) {
+            if let Ok(frame) = frame_of(tag, reply) {
+                send_message(sender, &frame).ok();
+            }
+        }
+    }
+}
+
+/// Send a typed request to `dest` and block for the matching tagged
+/// reply, copying its payload into `resp_buf` and returning its length.
+pub fn call<T: AsBytes>(
+    dest: u32,
+    tag: u16,
+    req: &T,
+    resp_buf: &mut [u8],
+) -> Result<usize, SysError> {
+    send_typed(dest, tag, req)?;
+
+    let mut buf = [0u8; MAX_FRAME];
+    loop {
+        let (sender, len) = receive_message(&mut buf)?;
+        if sender != dest || len < 2 {
+            continue;
+        }
+
+        let reply_tag = u16::from_le_bytes([buf[0], buf[1]]);
+        if reply_tag != tag {
+            continue;
+        }
+
+        let payload_len = len - 2;
+        if payload_len > resp_buf.len() {
+            return Err(SysError::InvalidArgument);
+        }
+        resp_buf[..payload_len].copy_from_slice(&buf[2..len]);
+        return Ok(payload_len);
+    }
+}
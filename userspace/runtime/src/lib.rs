@@ -7,7 +7,29 @@
 
 use core::panic::PanicInfo;
 use core::arch::asm;
-use heapless::String;
+
+mod line_editor;
+pub use line_editor::LineEditor;
+
+mod expr;
+pub use expr::{eval_expr, CalcError};
+
+mod error;
+pub use error::SysError;
+
+mod console;
+pub use console::{print_fmt, ConsoleWriter};
+
+mod time;
+pub use time::Instant;
+
+mod ipc;
+pub use ipc::{call, send_typed, serve, AsBytes, Response};
+
+mod heap;
+
+mod test_harness;
+pub use test_harness::{test_runner, Testable};
 
 /// System call numbers for MTOS
 #[repr(u32)]
@@ -23,6 +45,7 @@ pub enum SysCall {
     Free = 7,
     SendMessage = 8,
     ReceiveMessage = 9,
+    GetTime = 10,
 }
 
 /// System call interface
@@ -80,21 +103,35 @@ pub unsafe fn syscall3(call: SysCall, arg1: usize, arg2: usize, arg3: usize) ->
     ret
 }
 
+/// Read up to `buf.len()` bytes from the console into `buf`, returning the
+/// number of bytes read
+pub fn read(buf: &mut [u8]) -> Result<usize, SysError> {
+    let result = unsafe {
+        syscall2(SysCall::Read, buf.as_mut_ptr() as usize, buf.len())
+    };
+
+    if result >= 0 {
+        Ok(result as usize)
+    } else {
+        Err(SysError::from_errno(result))
+    }
+}
+
 /// Print a string to the console
-pub fn print(s: &str) -> Result<(), i32> {
+pub fn print(s: &str) -> Result<(), SysError> {
     let result = unsafe {
         syscall2(SysCall::Print, s.as_ptr() as usize, s.len())
     };
-    
+
     if result >= 0 {
         Ok(())
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
 /// Print a string with a newline
-pub fn println(s: &str) -> Result<(), i32> {
+pub fn println(s: &str) -> Result<(), SysError> {
     print(s)?;
     print("\n")
 }
@@ -115,70 +152,90 @@ pub fn getpid() -> u32 {
     }
 }
 
+/// Read the monotonic uptime in milliseconds since boot.
+///
+/// `isize` is 32-bit here, so the `GetTime` syscall hands back one half
+/// of the 64-bit tick counter per call: arg `0` for the low word, arg `1`
+/// for the high word. Reading the two halves takes two separate calls,
+/// so the low word can roll over between them; guard against that torn
+/// read seqlock-style by reading the high word before and after the low
+/// word and retrying if it changed.
+pub fn uptime_ms() -> u64 {
+    loop {
+        let high1 = unsafe { syscall1(SysCall::GetTime, 1) } as u32;
+        let low = unsafe { syscall1(SysCall::GetTime, 0) } as u32;
+        let high2 = unsafe { syscall1(SysCall::GetTime, 1) } as u32;
+
+        if high1 == high2 {
+            return ((high1 as u64) << 32) | low as u64;
+        }
+    }
+}
+
 /// Sleep for specified milliseconds
-pub fn sleep_ms(ms: u32) -> Result<(), i32> {
+pub fn sleep_ms(ms: u32) -> Result<(), SysError> {
     let result = unsafe {
         syscall1(SysCall::Sleep, ms as usize)
     };
-    
+
     if result >= 0 {
         Ok(())
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
 /// Allocate memory
-pub fn malloc(size: usize) -> Result<*mut u8, i32> {
+pub fn malloc(size: usize) -> Result<*mut u8, SysError> {
     let result = unsafe {
         syscall1(SysCall::Malloc, size)
     };
-    
+
     if result > 0 {
         Ok(result as *mut u8)
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
 /// Free memory
-pub fn free(ptr: *mut u8) -> Result<(), i32> {
+pub fn free(ptr: *mut u8) -> Result<(), SysError> {
     let result = unsafe {
         syscall1(SysCall::Free, ptr as usize)
     };
-    
+
     if result >= 0 {
         Ok(())
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
 /// Send a message via IPC
-pub fn send_message(dest_pid: u32, msg: &[u8]) -> Result<(), i32> {
+pub fn send_message(dest_pid: u32, msg: &[u8]) -> Result<(), SysError> {
     let result = unsafe {
         syscall3(SysCall::SendMessage, dest_pid as usize, msg.as_ptr() as usize, msg.len())
     };
-    
+
     if result >= 0 {
         Ok(())
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
 /// Receive a message via IPC
-pub fn receive_message(buffer: &mut [u8]) -> Result<(u32, usize), i32> {
+pub fn receive_message(buffer: &mut [u8]) -> Result<(u32, usize), SysError> {
     let result = unsafe {
         syscall2(SysCall::ReceiveMessage, buffer.as_mut_ptr() as usize, buffer.len())
     };
-    
+
     if result >= 0 {
         let sender_pid = (result >> 16) as u32;
         let msg_len = (result & 0xFFFF) as usize;
         Ok((sender_pid, msg_len))
     } else {
-        Err(result as i32)
+        Err(SysError::from_errno(result))
     }
 }
 
@@ -186,100 +243,82 @@ pub fn receive_message(buffer: &mut [u8]) -> Result<(u32, usize), i32> {
 #[panic_handler]
 fn panic(info: &PanicInfo) -> ! {
     if let Some(s) = info.payload().downcast_ref::<&str>() {
-        let _ = println(&format!("PANIC: {}", s));
+        print_fmt(format_args!("PANIC: {}\n", s));
     } else {
-        let _ = println("PANIC: (no message)");
+        print_fmt(format_args!("PANIC: (no message)\n"));
     }
-    
+
     if let Some(location) = info.location() {
-        let _ = println(&format!("  at {}:{}", location.file(), location.line()));
+        print_fmt(format_args!("  at {}:{}\n", location.file(), location.line()));
     }
-    
+
     exit(-1);
 }
 
-/// Global allocator interface (stub for now)
-pub struct MTOSAllocator;
+/// Global allocator backed by a userspace free-list heap over kernel-provided arenas
+pub struct MTOSAllocator {
+    heap: core::cell::UnsafeCell<heap::Heap>,
+}
+
+// Single-threaded userspace processes only; there is no thread support in
+// this runtime, so the heap is never accessed concurrently.
+unsafe impl Sync for MTOSAllocator {}
 
 unsafe impl core::alloc::GlobalAlloc for MTOSAllocator {
     unsafe fn alloc(&self, layout: core::alloc::Layout) -> *mut u8 {
-        match malloc(layout.size()) {
-            Ok(ptr) => ptr,
-            Err(_) => core::ptr::null_mut(),
-        }
+        (*self.heap.get()).alloc(layout)
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, _layout: core::alloc::Layout) {
-        let _ = free(ptr);
+        (*self.heap.get()).dealloc(ptr);
     }
 }
 
 #[global_allocator]
-static ALLOCATOR: MTOSAllocator = MTOSAllocator;
+static ALLOCATOR: MTOSAllocator = MTOSAllocator {
+    heap: core::cell::UnsafeCell::new(heap::Heap::empty()),
+};
 
 /// Application entry point macro
+///
+/// In a test build, `_start` runs the `#[test_case]` harness (generated
+/// as `test_main` by `#![reexport_test_harness_main = "test_main"]`)
+/// instead of `$main`.
 #[macro_export]
 macro_rules! mtos_main {
     ($main:expr) => {
+        #[cfg(not(test))]
         #[no_mangle]
         pub extern "C" fn _start() -> ! {
             let result = $main();
             $crate::exit(result);
         }
+
+        #[cfg(test)]
+        #[no_mangle]
+        pub extern "C" fn _start() -> ! {
+            test_main();
+            $crate::exit(0);
+        }
     };
 }
 
-// Helper formatting functions (since we can't use std::fmt)
-pub fn format_u32(value: u32) -> String<32> {
-    let mut result = String::new();
-    if value == 0 {
-        result.push('0').ok();
-        return result;
-    }
-    
-    let mut val = value;
-    let mut digits = heapless::Vec::<u8, 32>::new();
-    
-    while val > 0 {
-        digits.push((val % 10) as u8 + b'0').ok();
-        val /= 10;
-    }
-    
-    for digit in digits.iter().rev() {
-        result.push(*digit as char).ok();
-    }
-    
-    result
-}
+/// Parse a decimal string into a `u32`, rejecting non-digit characters and overflow
+pub fn parse_u32(s: &str) -> Result<u32, SysError> {
+    let mut result = 0u32;
 
-/// Simple string formatting function
-pub fn format(template: &str, value: u32) -> String<64> {
-    let mut result = String::new();
-    let value_str = format_u32(value);
-    
-    // Simple string substitution (replace first {} with value)
-    let mut chars = template.chars();
-    while let Some(ch) = chars.next() {
-        if ch == '{' {
-            // Look for closing brace
-            if let Some(next_ch) = chars.next() {
-                if next_ch == '}' {
-                    // Insert the value
-                    for value_ch in value_str.chars() {
-                        result.push(value_ch).ok();
-                    }
-                } else {
-                    // Not a placeholder, add both characters
-                    result.push(ch).ok();
-                    result.push(next_ch).ok();
-                }
-            } else {
-                result.push(ch).ok();
-            }
+    for ch in s.chars() {
+        if let Some(digit) = ch.to_digit(10) {
+            result = result
+                .checked_mul(10)
+                .ok_or(SysError::InvalidArgument)?;
+            result = result
+                .checked_add(digit)
+                .ok_or(SysError::InvalidArgument)?;
         } else {
-            result.push(ch).ok();
+            return Err(SysError::InvalidArgument);
         }
     }
-    
-    result
+
+    Ok(result)
 }
@@ -0,0 +1,142 @@
+//! Interactive line editor built on top of the raw `read` syscall wrapper.
+//!
+//! Handles backspace and keeps a small ring buffer of previous lines
+//! recallable with the up/down arrow escape sequences.
+
+use heapless::{String, Vec};
+
+use crate::{print, read};
+
+const BACKSPACE: u8 = 0x08;
+const DEL: u8 = 0x7f;
+const ESC: u8 = 0x1b;
+
+enum HistoryMove {
+    Older,
+    Newer,
+}
+
+/// Line editor with a fixed-size history ring buffer.
+///
+/// `N` bounds the length of a single line, `H` bounds how many previous
+/// lines are retained for recall.
+pub struct LineEditor<const N: usize, const H: usize> {
+    history: Vec<String<N>, H>,
+}
+
+impl<const N: usize, const H: usize> LineEditor<N, H> {
+    pub fn new() -> Self {
+        Self {
+            history: Vec::new(),
+        }
+    }
+
+    /// Print `prompt`, then read a line byte-by-byte until Enter is
+    /// pressed, echoing input and honoring backspace and history recall.
+    pub fn readline(&mut self, prompt: &str) -> String<N> {
+        print(prompt).ok();
+
+        let mut line: String<N> = String::new();
+        let mut history_cursor = self.history.len();
+        let mut byte = [0u8; 1];
+
+        loop {
+            match read(&mut byte) {
+                Ok(1) => {}
+                _ => continue,
+            }
+
+            match byte[0] {
+                b'\r' | b'\n' => {
+                    print("\n").ok();
+                    break;
+                }
+                BACKSPACE | DEL => {
+                    if line.pop().is_some() {
+                        print("\u{8} \u{8}").ok();
+                    }
+                }
+                ESC => {
+                    if let Some(direction) = Self::read_arrow() {
+                        self.recall_history(direction, &mut line, &mut history_cursor);
+                    }
+                }
+                ch if ch.is_ascii_graphic() || ch == b' ' => {
+                    if line.push(ch as char).is_ok() {
+                        echo_byte(ch);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !line.is_empty() {
+            if self.history.is_full() {
+                self.history.remove(0);
+            }
+            let _ = self.history.push(line.clone());
+        }
+
+        line
+    }
+
+    fn read_arrow() -> Option<HistoryMove> {
+        let mut byte = [0u8; 1];
+        if read(&mut byte) != Ok(1) || byte[0] != b'[' {
+            return None;
+        }
+        if read(&mut byte) != Ok(1) {
+            return None;
+        }
+        match byte[0] {
+            b'A' => Some(HistoryMove::Older),
+            b'B' => Some(HistoryMove::Newer),
+            _ => None,
+        }
+    }
+
+    fn recall_history(
+        &self,
+        direction: HistoryMove,
+        line: &mut String<N>,
+        history_cursor: &mut usize,
+    ) {
+        match direction {
+            HistoryMove::Older => {
+                if *history_cursor > 0 {
+                    *history_cursor -= 1;
+                }
+            }
+            HistoryMove::Newer => {
+                if *history_cursor < self.history.len() {
+                    *history_cursor += 1;
+                }
+            }
+        }
+
+        let replacement = self
+            .history
+            .get(*history_cursor)
+            .cloned()
+            .unwrap_or_default();
+
+        for _ in 0..line.len() {
+            print("\u{8} \u{8}").ok();
+        }
+        print(&replacement).ok();
+        *line = replacement;
+    }
+}
+
+impl<const N: usize, const H: usize> Default for LineEditor<N, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn echo_byte(ch: u8) {
+    let buf = [ch];
+    if let Ok(s) = core::str::from_utf8(&buf) {
+        print(s).ok();
+    }
+}
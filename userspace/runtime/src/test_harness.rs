@@ -0,0 +1,70 @@
+//! Lightweight `#[test_case]`-based harness for running userspace apps as
+//! automated tests, since there is no host to drive `cargo test` against
+//! a `no_std` binary directly.
+//!
+//! Pair this with, at the app crate root:
+//! ```ignore
+//! #![cfg_attr(test, feature(custom_test_frameworks))]
+//! #![cfg_attr(test, test_runner(mtos_runtime::test_runner))]
+//! #![cfg_attr(test, reexport_test_harness_main = "test_main")]
+//! ```
+
+/// A single test case. Blanket-implemented for any zero-argument `Fn`
+/// returning `bool`, so a plain `#[test_case] fn ...() -> bool { ... }`
+/// works directly. Combine multiple `assert_eq_u32!` calls with `&`
+/// rather than `&&` so every assertion in the case is printed and
+/// tallied, instead of short-circuiting at the first mismatch.
+pub trait Testable {
+    fn run(&self) -> bool;
+}
+
+impl<T: Fn() -> bool> Testable for T {
+    fn run(&self) -> bool {
+        crate::print!("{} ... ", core::any::type_name::<T>());
+        let passed = self();
+        crate::println!("{}", if passed { "ok" } else { "FAILED" });
+        passed
+    }
+}
+
+/// Run every registered test case, printing `ok` or `FAILED` per case,
+/// then exit `0` if all cases passed or `1` if any failed. A failing case
+/// no longer aborts the run, so every case always gets to execute.
+pub fn test_runner(tests: &[&dyn Testable]) -> ! {
+    crate::println!("Running {} tests", tests.len());
+
+    let mut failed = 0;
+    for test in tests {
+        if !test.run() {
+            failed += 1;
+        }
+    }
+
+    if failed == 0 {
+        crate::println!("All {} tests passed", tests.len());
+        crate::exit(0);
+    } else {
+        crate::println!("{} of {} tests FAILED", failed, tests.len());
+        crate::exit(1);
+    }
+}
+
+/// Assert two `u32` values are equal, printing both expected and actual
+/// on mismatch and evaluating to whether they matched. Combine multiple
+/// calls with `&` (not the short-circuiting `&&`) so a test case reports
+/// every assertion instead of stopping at the first failure.
+#[macro_export]
+macro_rules! assert_eq_u32 {
+    ($expected:expr, $actual:expr) => {{
+        let expected_val: u32 = $expected;
+        let actual_val: u32 = $actual;
+        if expected_val != actual_val {
+            $crate::println!("FAILED");
+            $crate::println!("  expected: {}", expected_val);
+            $crate::println!("  actual:   {}", actual_val);
+            false
+        } else {
+            true
+        }
+    }};
+}
@@ -0,0 +1,26 @@
+//! High-level timing on top of the `GetTime` syscall.
+
+use crate::uptime_ms;
+
+/// A point in monotonic time, for measuring elapsed durations.
+#[derive(Copy, Clone, Debug)]
+pub struct Instant {
+    ticks_ms: u64,
+}
+
+impl Instant {
+    /// Capture the current monotonic time.
+    pub fn now() -> Self {
+        Self {
+            ticks_ms: uptime_ms(),
+        }
+    }
+
+    /// Milliseconds elapsed since this instant was captured.
+    ///
+    /// Saturates to zero rather than wrapping if the underlying tick
+    /// counter has wrapped around since `now()` was called.
+    pub fn elapsed_ms(&self) -> u64 {
+        uptime_ms().saturating_sub(self.ticks_ms)
+    }
+}
@@ -2,185 +2,189 @@
 #![no_main]
 
 //! Simple shell userspace application for MTOS
-//! 
+//!
 //! Demonstrates interactive userspace application development
 //! and basic command processing.
 
-use mtos_runtime::{println, getpid, sleep_ms, mtos_main, format_u32};
-use heapless::{String, Vec};
+use mtos_runtime::{println, print, getpid, mtos_main, eval_expr, parse_u32, call, CalcError, Instant, LineEditor, SysError};
+
+/// Maximum length of a single shell line.
+const LINE_LEN: usize = 128;
+/// Number of previous lines recallable with the up/down arrow keys.
+const HISTORY_LEN: usize = 8;
+
+/// PID the kernel launches the `sysinfo` server at before handing off
+/// to the shell; there's no service-discovery protocol yet, so `info`
+/// just assumes this fixed PID.
+const SYSINFO_SERVER_PID: u32 = 2;
+/// IPC tag for an info request; must match `sysinfo`'s `INFO_TAG`.
+const INFO_TAG: u16 = 1;
 
 fn main() -> i32 {
-    println("🐚 MTOS Simple Shell").unwrap();
-    println("====================").unwrap();
-    println("").unwrap();
-    
+    println!("🐚 MTOS Simple Shell");
+    println!("====================");
+    println!();
+
     let pid = getpid();
-    println(&format!("Shell running as PID: {}", format_u32(pid))).unwrap();
-    println("").unwrap();
-    
-    println("📋 Available commands:").unwrap();
-    println("  help     - Show this help message").unwrap();
-    println("  info     - Show system information").unwrap();
-    println("  echo <text> - Echo back the text").unwrap();
-    println("  calc <a> <op> <b> - Simple calculator").unwrap();
-    println("  sleep <ms> - Sleep for specified milliseconds").unwrap();
-    println("  mem      - Test memory allocation").unwrap();
-    println("  exit     - Exit the shell").unwrap();
-    println("").unwrap();
-    
-    // Main shell loop (simulated - no real input in this demo)
-    println("🔄 Simulating shell session:").unwrap();
-    println("").unwrap();
-    
-    // Simulate some commands
-    let demo_commands = [
-        "help",
-        "info", 
-        "echo Hello MTOS!",
-        "calc 15 + 27",
-        "calc 100 / 7",
-        "mem",
-        "sleep 500",
-        "exit"
-    ];
-    
-    for command in demo_commands.iter() {
-        println(&format!("mtos$ {}", command)).unwrap();
-        execute_command(command);
-        println("").unwrap();
-        
-        // Small delay between commands for demo effect
-        sleep_ms(200).ok();
+    println!("Shell running as PID: {}", pid);
+    println!();
+
+    println!("📋 Available commands:");
+    println!("  help     - Show this help message");
+    println!("  info     - Show system information");
+    println!("  echo <text> - Echo back the text");
+    println!("  calc <expr> - Evaluate an arithmetic expression");
+    println!("  sleep <ms> - Sleep for specified milliseconds");
+    println!("  time <command> - Report how long a command took");
+    println!("  mem      - Test memory allocation");
+    println!("  exit     - Exit the shell");
+    println!();
+    println!("Use ↑/↓ to recall previous commands.");
+    println!();
+
+    let mut editor: LineEditor<LINE_LEN, HISTORY_LEN> = LineEditor::new();
+
+    loop {
+        let line = editor.readline("mtos$ ");
+        let command = line.trim();
+
+        if command.is_empty() {
+            continue;
+        }
+
+        if execute_command(command) {
+            break;
+        }
     }
-    
-    println("👋 Shell session ended").unwrap();
+
+    println!("👋 Shell session ended");
     0
 }
 
-fn execute_command(command: &str) {
+/// Run a single command. Returns `true` if the shell should exit.
+fn execute_command(command: &str) -> bool {
     let mut parts = command.split_whitespace();
-    
+
     match parts.next() {
         Some("help") => {
-            println("📖 Shell Help:").unwrap();
-            println("This is a demonstration shell for MTOS.").unwrap();
-            println("It shows how userspace applications can").unwrap();
-            println("interact with the kernel through system calls.").unwrap();
+            println!("📖 Shell Help:");
+            println!("This is a demonstration shell for MTOS.");
+            println!("It shows how userspace applications can");
+            println!("interact with the kernel through system calls.");
         }
-        
+
         Some("info") => {
-            println("💻 System Information:").unwrap();
-            let pid = getpid();
-            println(&format!("Current PID: {}", format_u32(pid))).unwrap();
-            println("OS: MTOS (Modular Teaching OS)").unwrap();
-            println("Architecture: Educational x86").unwrap();
-            println("Userspace Language: Rust").unwrap();
+            println!("💻 System Information:");
+            match query_sysinfo_pid() {
+                Ok(pid) => println!("sysinfo server PID: {}", pid),
+                Err(e) => println!("❌ sysinfo server unreachable: {}", e),
+            }
+            println!("OS: MTOS (Modular Teaching OS)");
+            println!("Architecture: Educational x86");
+            println!("Userspace Language: Rust");
         }
-        
+
         Some("echo") => {
-            print("🔊 ");
+            print!("🔊 ");
             for word in parts {
-                print(word);
-                print(" ");
+                print!("{} ", word);
             }
-            println("").unwrap();
+            println!();
         }
-        
+
         Some("calc") => {
-            if let Some(a_str) = parts.next() {
-                if let Some(op) = parts.next() {
-                    if let Some(b_str) = parts.next() {
-                        if let (Ok(a), Ok(b)) = (parse_u32(a_str), parse_u32(b_str)) {
-                            let result = match op {
-                                "+" => Some(a + b),
-                                "-" => if a >= b { Some(a - b) } else { None },
-                                "*" => Some(a * b),
-                                "/" => if b != 0 { Some(a / b) } else { None },
-                                "%" => if b != 0 { Some(a % b) } else { None },
-                                _ => None
-                            };
-                            
-                            match result {
-                                Some(r) => println(&format!("🧮 {} {} {} = {}", 
-                                                          format_u32(a), op, format_u32(b), format_u32(r))).unwrap(),
-                                None => println("❌ Invalid operation or division by zero").unwrap(),
-                            }
-                        } else {
-                            println("❌ Invalid numbers").unwrap();
-                        }
-                    } else {
-                        println("❌ Missing second number").unwrap();
-                    }
-                } else {
-                    println("❌ Missing operator").unwrap();
-                }
+            let expr = command["calc".len()..].trim();
+            if expr.is_empty() {
+                println!("❌ Missing expression");
             } else {
-                println("❌ Missing first number").unwrap();
+                match eval_expr(expr) {
+                    Ok(result) => println!("🧮 {} = {}", expr, result),
+                    Err(e) => println!("❌ {}", describe_calc_error(e)),
+                }
             }
         }
-        
+
         Some("sleep") => {
             if let Some(ms_str) = parts.next() {
                 if let Ok(ms) = parse_u32(ms_str) {
-                    println(&format!("😴 Sleeping for {} ms...", format_u32(ms))).unwrap();
-                    match sleep_ms(ms) {
-                        Ok(_) => println("⏰ Wake up!").unwrap(),
-                        Err(e) => println(&format!("❌ Sleep failed: {}", e)).unwrap(),
+                    println!("😴 Sleeping for {} ms...", ms);
+                    match mtos_runtime::sleep_ms(ms) {
+                        Ok(_) => println!("⏰ Wake up!"),
+                        Err(e) => println!("❌ Sleep failed: {}", e),
                     }
                 } else {
-                    println("❌ Invalid sleep duration").unwrap();
+                    println!("❌ Invalid sleep duration");
                 }
             } else {
-                println("❌ Missing sleep duration").unwrap();
+                println!("❌ Missing sleep duration");
+            }
+        }
+
+        Some("time") => {
+            let inner = command["time".len()..].trim();
+            if inner.is_empty() {
+                println!("❌ Missing command to time");
+            } else {
+                let start = Instant::now();
+                let should_exit = execute_command(inner);
+                println!("⏱️ '{}' took {} ms", inner, start.elapsed_ms());
+                if should_exit {
+                    return true;
+                }
             }
         }
-        
+
         Some("mem") => {
-            println("🧠 Testing memory allocation...").unwrap();
+            println!("🧠 Testing memory allocation...");
             match mtos_runtime::malloc(512) {
                 Ok(ptr) => {
-                    println("✅ Allocated 512 bytes").unwrap();
+                    println!("✅ Allocated 512 bytes");
                     match mtos_runtime::free(ptr) {
-                        Ok(_) => println("✅ Memory freed successfully").unwrap(),
-                        Err(e) => println(&format!("⚠️ Failed to free memory: {}", e)).unwrap(),
+                        Ok(_) => println!("✅ Memory freed successfully"),
+                        Err(e) => println!("⚠️ Failed to free memory: {}", e),
                     }
                 }
-                Err(e) => println(&format!("❌ Allocation failed: {}", e)).unwrap(),
+                Err(e) => println!("❌ Allocation failed: {}", e),
             }
         }
-        
+
         Some("exit") => {
-            println("👋 Goodbye!").unwrap();
+            println!("👋 Goodbye!");
+            return true;
         }
-        
+
         Some(unknown) => {
-            println(&format!("❓ Unknown command: {}", unknown)).unwrap();
-            println("Type 'help' for available commands").unwrap();
+            println!("❓ Unknown command: {}", unknown);
+            println!("Type 'help' for available commands");
         }
-        
+
         None => {
             // Empty command, do nothing
         }
     }
+
+    false
 }
 
-fn print(s: &str) {
-    mtos_runtime::print(s).ok();
+/// Query the `sysinfo` server's PID over IPC rather than calling
+/// `getpid` inline.
+fn query_sysinfo_pid() -> Result<u32, SysError> {
+    let mut buf = [0u8; 4];
+    let len = call(SYSINFO_SERVER_PID, INFO_TAG, &0u32, &mut buf)?;
+    if len != 4 {
+        return Err(SysError::InvalidArgument);
+    }
+    Ok(u32::from_le_bytes(buf))
 }
 
-fn parse_u32(s: &str) -> Result<u32, ()> {
-    let mut result = 0u32;
-    
-    for ch in s.chars() {
-        if let Some(digit) = ch.to_digit(10) {
-            result = result.checked_mul(10).ok_or(())?;
-            result = result.checked_add(digit).ok_or(())?;
-        } else {
-            return Err(());
-        }
+fn describe_calc_error(error: CalcError) -> &'static str {
+    match error {
+        CalcError::MismatchedParens => "Mismatched parentheses",
+        CalcError::DivisionByZero => "Division by zero",
+        CalcError::UnexpectedToken => "Unexpected token in expression",
+        CalcError::TooManyTokens => "Expression too long",
+        CalcError::EmptyExpression => "Empty expression",
     }
-    
-    Ok(result)
 }
 
 mtos_main!(main);
@@ -0,0 +1,27 @@
+#![no_std]
+#![no_main]
+
+//! System info server for MTOS
+//!
+//! Answers the `info` IPC tag with this process's PID, so `shell`'s
+//! `info` command can query it through `mtos_runtime::call` instead of
+//! calling `getpid` inline.
+
+use mtos_runtime::{getpid, mtos_main, serve, Response};
+
+/// IPC tag for an info request; `shell` calls with this same value.
+pub const INFO_TAG: u16 = 1;
+
+fn main() -> i32 {
+    let pid_bytes = getpid().to_le_bytes();
+
+    serve(|_sender, tag, _payload| {
+        if tag == INFO_TAG {
+            Response::Reply(&pid_bytes)
+        } else {
+            Response::None
+        }
+    })
+}
+
+mtos_main!(main);